@@ -3,9 +3,15 @@ extern crate getopts;
 use getopts::Options;
 use std::env;
 use std::error::Error;
+use std::fmt;
 use std::fs::File;
 use std::io::BufReader;
 use std::io::Read;
+use unicode_width::UnicodeWidthChar;
+
+/// Size of the blocks inputs are streamed in, so processing a file's
+/// memory use doesn't scale with its size.
+const CHUNK_SIZE: usize = 64 * 1024;
 
 #[derive(Default)]
 struct WCCmd {
@@ -21,25 +27,52 @@ struct WCCmd {
     /// flag to enable the count of characters
     chars: bool,
 
+    /// flag to enable reporting the length of the longest line
+    max_line: bool,
+
     /// input files to be processed (empty if stdin)
     inputs: Vec<WCInput>,
 
+    /// when to print the grand total line across all inputs
+    total: WCTotalWhen,
+
     /// output processed
     outputs: Vec<WCOutput>,
 }
 
 impl WCCmd {
     fn show(&self) {
+        if self.total.show_per_file() {
+            for o in &self.outputs {
+                println!("{}", o.as_string(self))
+            }
+        }
+        if self.total.show_total(self.inputs.len()) {
+            println!("{}", self.total_output().as_string(self))
+        }
+    }
+
+    /// Sums every per-input `WCOutput` into a synthetic `total` row.
+    fn total_output(&self) -> WCOutput {
+        let mut total = WCOutput {
+            filename: Some("total".to_string()),
+            ..WCOutput::default()
+        };
         for o in &self.outputs {
-            println!("{}", o.as_string(self))
+            total.line_ct += o.line_ct;
+            total.word_ct += o.word_ct;
+            total.byte_ct += o.byte_ct;
+            total.char_ct += o.char_ct;
+            total.max_line_len = total.max_line_len.max(o.max_line_len);
         }
+        total
     }
 
     fn use_default_flags(&self) -> bool {
-        !self.chars && !self.lines && !self.words && !self.bytes
+        !self.chars && !self.lines && !self.words && !self.bytes && !self.max_line
     }
 
-    fn from_args(args: Vec<String>) -> Result<Self, getopts::Fail> {
+    fn from_args(args: Vec<String>) -> Result<Self, Box<dyn Error>> {
         let mut opts = Options::new();
 
         // define the options
@@ -47,77 +80,337 @@ impl WCCmd {
         opts.optflag("l", "lines", "count the number of lines");
         opts.optflag("w", "words", "count the number of words");
         opts.optflag("m", "chars", "count the number of chars");
+        opts.optflag(
+            "L",
+            "max-line-length",
+            "print the length of the longest line",
+        );
+        opts.optopt(
+            "",
+            "files0-from",
+            "read input from the NUL-separated list of file names in FILE ('-' for stdin)",
+            "FILE",
+        );
+        opts.optopt(
+            "",
+            "total",
+            "when to print a line with total counts (auto, always, never, only)",
+            "WHEN",
+        );
 
         // parse the options
         let opts_matches = opts.parse(&args[1..])?;
         let arg_inputs = opts_matches.free.to_owned();
-        let mut parsed_inputs = Vec::new();
+        let files0_from = opts_matches.opt_str("files0-from");
+        let total = match opts_matches.opt_str("total") {
+            Some(s) => WCTotalWhen::parse(&s)?,
+            None => WCTotalWhen::default(),
+        };
+
+        if files0_from.is_some() && !arg_inputs.is_empty() {
+            return Err(Box::new(WCArgsError(
+                "extra operand after --files0-from".to_string(),
+            )));
+        }
 
-        if !arg_inputs.is_empty() {
-            for f in arg_inputs {
-                parsed_inputs.push(WCInput::File(f.to_string()));
-            }
+        let parsed_inputs = if let Some(f) = files0_from {
+            Self::parse_files0_from(&f)?
+        } else if !arg_inputs.is_empty() {
+            arg_inputs.into_iter().map(WCInput::File).collect()
         } else {
-            parsed_inputs.push(WCInput::StdIn())
-        }
+            vec![WCInput::StdIn()]
+        };
 
         Ok(WCCmd {
             bytes: opts_matches.opt_present("c"),
             lines: opts_matches.opt_present("l"),
             words: opts_matches.opt_present("w"),
             chars: opts_matches.opt_present("m"),
+            max_line: opts_matches.opt_present("L"),
             inputs: parsed_inputs,
+            total,
             outputs: Vec::new(),
         })
     }
 
-    fn process(&mut self) -> std::io::Result<()> {
-        let mut buffer: Vec<u8> = Vec::new();
-        let default = self.use_default_flags();
+    /// Reads `file` (or stdin when `file` is "-") and splits its contents on
+    /// NUL bytes to build the list of inputs, mirroring GNU wc's
+    /// `--files0-from`.
+    fn parse_files0_from(file: &str) -> Result<Vec<WCInput>, Box<dyn Error>> {
+        let bytes = if file == "-" {
+            let mut buf = Vec::new();
+            std::io::stdin().read_to_end(&mut buf)?;
+            buf
+        } else {
+            std::fs::read(file)?
+        };
 
-        for input in &mut self.inputs {
-            let mut output = WCOutput::default();
+        let mut names: Vec<&[u8]> = bytes.split(|b| *b == b'\0').collect();
+        if names.last().map(|s| s.is_empty()).unwrap_or(false) {
+            names.pop();
+        }
 
-            input.as_buffer(&mut buffer)?;
-            output.filename = input.path();
+        let mut inputs = Vec::with_capacity(names.len());
+        for name in names {
+            if name.is_empty() {
+                return Err(Box::new(WCArgsError(
+                    "invalid zero-length file name".to_string(),
+                )));
+            }
+            let name = String::from_utf8_lossy(name).into_owned();
+            if name == "-" {
+                inputs.push(WCInput::StdIn());
+            } else {
+                inputs.push(WCInput::File(name));
+            }
+        }
+        Ok(inputs)
+    }
 
-            let mut parsing_word = true;
-            for b in buffer.iter() {
-                if self.bytes || default {
-                    output.byte_ct += 1;
+    /// Processes every input independently, printing a GNU-style
+    /// diagnostic to stderr and moving on when one fails, so the other
+    /// inputs still produce output. Returns `true` if any input failed.
+    ///
+    /// Inputs are streamed through in `CHUNK_SIZE` blocks rather than
+    /// slurped whole, so a multi-gigabyte file uses constant memory. When
+    /// only bytes and/or lines are requested, `count_fast` takes a
+    /// vectorized path; otherwise `count_general` decodes the input as
+    /// UTF-8 and counts per character.
+    fn process(&mut self) -> bool {
+        let default = self.use_default_flags();
+        let fast_path = !default && !self.words && !self.chars && !self.max_line;
+        let mut had_error = false;
+
+        for input in &self.inputs {
+            let path = input.path().unwrap_or_else(|| "-".to_string());
+
+            let mut reader = match input.reader() {
+                Ok(r) => r,
+                Err(e) => {
+                    eprintln!("ccwc: {e}");
+                    had_error = true;
+                    continue;
                 }
-                if (self.lines || default) && *b == b'\n' {
-                    output.line_ct += 1;
+            };
+
+            let mut output = WCOutput {
+                filename: input.path(),
+                ..WCOutput::default()
+            };
+
+            let result = if fast_path {
+                Self::count_fast(reader.as_mut(), self.bytes, self.lines, &mut output)
+            } else {
+                let flags = WCCountFlags {
+                    count_bytes: self.bytes,
+                    count_lines: self.lines,
+                    count_words: self.words,
+                    count_chars: self.chars,
+                    count_max_line: self.max_line,
+                    default,
+                };
+                Self::count_general(reader.as_mut(), flags, &mut output)
+            };
+
+            match result {
+                Ok(()) => self.outputs.push(output),
+                Err(e) => {
+                    eprintln!("ccwc: {}", WCInputError::from_io(&path, e));
+                    had_error = true;
                 }
+            }
+        }
+        had_error
+    }
 
-                if self.words || default {
-                    if parsing_word {
-                        if b.is_ascii_whitespace() {
-                            output.word_ct += 1;
-                            parsing_word = false;
-                        }
-                    } else if !b.is_ascii_whitespace() {
-                        parsing_word = true;
-                    }
-                }
+    /// Vectorized path for byte-only, line-only, or byte+line counting:
+    /// the byte count comes directly from the number of bytes read, and
+    /// newlines are counted per block with `bytecount::count`.
+    fn count_fast(
+        reader: &mut dyn Read,
+        count_bytes: bool,
+        count_lines: bool,
+        output: &mut WCOutput,
+    ) -> std::io::Result<()> {
+        let mut block = [0u8; CHUNK_SIZE];
+        loop {
+            let n = reader.read(&mut block)?;
+            if n == 0 {
+                break;
+            }
+            if count_bytes {
+                output.byte_ct += n as u64;
+            }
+            if count_lines {
+                output.line_ct += bytecount::count(&block[..n], b'\n') as u64;
             }
+        }
+        Ok(())
+    }
 
-            if self.chars {
-                match String::from_utf8(buffer.to_owned()) {
-                    Ok(s) => {
-                        output.char_ct = s.chars().count() as u64;
-                    }
-                    _ => {
-                        // if there is an error we fallback for bytes
-                        output.char_ct = output.byte_ct;
+    /// General path used whenever words, chars, or the max line length are
+    /// requested. Still folds one block at a time into the running counts,
+    /// carrying the in-word/end-of-line state across block boundaries.
+    fn count_general(
+        reader: &mut dyn Read,
+        flags: WCCountFlags,
+        output: &mut WCOutput,
+    ) -> std::io::Result<()> {
+        let WCCountFlags {
+            count_bytes,
+            count_lines,
+            count_words,
+            count_chars,
+            count_max_line,
+            default,
+        } = flags;
+        let want_lines = count_lines || default;
+        let want_words = count_words || default;
+
+        let mut block = [0u8; CHUNK_SIZE];
+        let mut decoder = Utf8Decoder::default();
+
+        let mut byte_ct: u64 = 0;
+        let mut line_ct: u64 = 0;
+        let mut word_ct: u64 = 0;
+        let mut char_ct: u64 = 0;
+        let mut parsing_word = false;
+        let mut line_width: u64 = 0;
+        let mut max_width: u64 = 0;
+
+        let mut on_char = |c: char| {
+            if want_lines && c == '\n' {
+                line_ct += 1;
+            }
+            if want_words {
+                if parsing_word {
+                    if c.is_whitespace() {
+                        word_ct += 1;
+                        parsing_word = false;
                     }
+                } else if !c.is_whitespace() {
+                    parsing_word = true;
                 }
             }
-            self.outputs.push(output);
-            buffer.clear();
+            if count_chars {
+                char_ct += 1;
+            }
+            if count_max_line {
+                Self::fold_line_width_char(c, &mut line_width, &mut max_width);
+            }
+        };
+
+        loop {
+            let n = reader.read(&mut block)?;
+            if n == 0 {
+                break;
+            }
+            if count_bytes || default {
+                byte_ct += n as u64;
+            }
+            decoder.feed(&block[..n], &mut on_char);
         }
+        decoder.finish(&mut on_char);
+
+        // the input may end mid-word (no trailing whitespace), in which
+        // case the word→whitespace transition above never fires
+        if want_words && parsing_word {
+            word_ct += 1;
+        }
+
+        output.byte_ct = byte_ct;
+        output.line_ct = line_ct;
+        output.word_ct = word_ct;
+        output.char_ct = char_ct;
+        if count_max_line {
+            output.max_line_len = max_width.max(line_width);
+        }
+
         Ok(())
     }
+
+    /// Folds a single decoded character into the running line `width`,
+    /// flushing into `max_width` on `\n` and expanding `\t` to the next
+    /// multiple of 8 columns.
+    fn fold_line_width_char(c: char, width: &mut u64, max_width: &mut u64) {
+        if c == '\n' {
+            *max_width = (*max_width).max(*width);
+            *width = 0;
+        } else if c == '\t' {
+            *width = (*width / 8 + 1) * 8;
+        } else {
+            *width += match UnicodeWidthChar::width(c) {
+                Some(w) => w as u64,
+                None => u64::from(!c.is_control()),
+            };
+        }
+    }
+}
+
+/// Which counts `count_general` should accumulate, bundled so the function
+/// doesn't need a long list of positional `bool` parameters.
+#[derive(Default)]
+struct WCCountFlags {
+    count_bytes: bool,
+    count_lines: bool,
+    count_words: bool,
+    count_chars: bool,
+    count_max_line: bool,
+
+    /// no flag was given at all, so the default lines/words/bytes trio
+    /// should be reported
+    default: bool,
+}
+
+/// Error produced while validating/parsing command-line arguments.
+#[derive(Debug)]
+struct WCArgsError(String);
+
+impl fmt::Display for WCArgsError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl Error for WCArgsError {}
+
+/// Controls whether/when the grand `total` line is printed, mirroring GNU
+/// wc's `--total=WHEN`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+enum WCTotalWhen {
+    /// print the total only when more than one input was given
+    #[default]
+    Auto,
+    /// always print the total, even for a single input
+    Always,
+    /// never print the total
+    Never,
+    /// print only the total, suppressing the per-file lines
+    Only,
+}
+
+impl WCTotalWhen {
+    fn parse(s: &str) -> Result<Self, WCArgsError> {
+        match s {
+            "auto" => Ok(WCTotalWhen::Auto),
+            "always" => Ok(WCTotalWhen::Always),
+            "never" => Ok(WCTotalWhen::Never),
+            "only" => Ok(WCTotalWhen::Only),
+            _ => Err(WCArgsError(format!("invalid --total argument '{s}'"))),
+        }
+    }
+
+    fn show_per_file(&self) -> bool {
+        !matches!(self, WCTotalWhen::Only)
+    }
+
+    fn show_total(&self, input_ct: usize) -> bool {
+        match self {
+            WCTotalWhen::Auto => input_ct > 1,
+            WCTotalWhen::Always | WCTotalWhen::Only => true,
+            WCTotalWhen::Never => false,
+        }
+    }
 }
 
 enum WCInput {
@@ -133,18 +426,128 @@ impl WCInput {
         }
     }
 
-    fn as_buffer(&mut self, buffer: &mut Vec<u8>) -> std::io::Result<()> {
+    /// Opens this input as a stream to be read in chunks, rather than
+    /// slurped whole.
+    fn reader(&self) -> Result<Box<dyn Read>, WCInputError> {
         match self {
             WCInput::File(f) => {
-                let file = File::open(f)?;
-                let mut reader = BufReader::new(file);
-                reader.read_to_end(buffer)?;
+                let file = File::open(f.as_str()).map_err(|e| WCInputError::from_io(f, e))?;
+                Ok(Box::new(BufReader::with_capacity(CHUNK_SIZE, file)))
             }
-            WCInput::StdIn() => {
-                std::io::stdin().read_to_end(buffer)?;
+            WCInput::StdIn() => Ok(Box::new(std::io::stdin())),
+        }
+    }
+}
+
+/// A failure to read a single input, carrying the offending path so it can
+/// be reported without aborting the rest of the run.
+#[derive(Debug)]
+enum WCInputError {
+    NotFound(String),
+    PermissionDenied(String),
+    IsADirectory(String),
+    Other(String, String),
+}
+
+impl WCInputError {
+    fn from_io(path: &str, err: std::io::Error) -> Self {
+        match err.kind() {
+            std::io::ErrorKind::NotFound => WCInputError::NotFound(path.to_string()),
+            std::io::ErrorKind::PermissionDenied => {
+                WCInputError::PermissionDenied(path.to_string())
             }
+            _ if err.raw_os_error() == Some(21) => WCInputError::IsADirectory(path.to_string()),
+            _ => WCInputError::Other(path.to_string(), err.to_string()),
+        }
+    }
+}
+
+impl fmt::Display for WCInputError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            WCInputError::NotFound(p) => {
+                write!(f, "{}: No such file or directory", quote_filename(p))
+            }
+            WCInputError::PermissionDenied(p) => {
+                write!(f, "{}: Permission denied", quote_filename(p))
+            }
+            WCInputError::IsADirectory(p) => write!(f, "{}: Is a directory", quote_filename(p)),
+            WCInputError::Other(p, reason) => write!(f, "{}: {}", quote_filename(p), reason),
+        }
+    }
+}
+
+impl Error for WCInputError {}
+
+/// Wraps `name` in single quotes, GNU-style, when it contains spaces or
+/// control characters that would otherwise make it ambiguous in a
+/// diagnostic message.
+fn quote_filename(name: &str) -> String {
+    if name.chars().any(|c| c == ' ' || c.is_control()) {
+        format!("'{name}'")
+    } else {
+        name.to_string()
+    }
+}
+
+/// Incrementally decodes UTF-8 as chunks of bytes arrive, so a multibyte
+/// code point split across a chunk boundary is reassembled rather than
+/// mangled. Invalid byte sequences are replaced with U+FFFD and decoding
+/// resyncs at the next byte, matching `String::from_utf8_lossy`'s
+/// behavior but without ever buffering the whole input.
+#[derive(Default)]
+struct Utf8Decoder {
+    /// bytes of a code point straddling the previous and current chunk
+    pending: Vec<u8>,
+}
+
+impl Utf8Decoder {
+    /// Feeds the next chunk of bytes, calling `on_char` for every decoded
+    /// character (including substituted replacement characters).
+    fn feed<F: FnMut(char)>(&mut self, chunk: &[u8], mut on_char: F) {
+        let mut data = std::mem::take(&mut self.pending);
+        data.extend_from_slice(chunk);
+
+        let mut start = 0;
+        loop {
+            match std::str::from_utf8(&data[start..]) {
+                Ok(s) => {
+                    s.chars().for_each(&mut on_char);
+                    data.clear();
+                    break;
+                }
+                Err(e) => {
+                    let valid_up_to = e.valid_up_to();
+                    std::str::from_utf8(&data[start..start + valid_up_to])
+                        .unwrap()
+                        .chars()
+                        .for_each(&mut on_char);
+
+                    match e.error_len() {
+                        Some(invalid_len) => {
+                            on_char('\u{FFFD}');
+                            start += valid_up_to + invalid_len;
+                        }
+                        None => {
+                            // the tail is an incomplete sequence; keep it
+                            // for the next chunk
+                            data.drain(..start + valid_up_to);
+                            break;
+                        }
+                    }
+                }
+            }
+        }
+        self.pending = data;
+    }
+
+    /// Flushes a still-pending, never-completed sequence as a single
+    /// replacement character once the input is exhausted.
+    fn finish<F: FnMut(char)>(&mut self, mut on_char: F) {
+        if !self.pending.is_empty() {
+            on_char('\u{FFFD}');
+            self.pending.clear();
         }
-        Ok(())
     }
 }
 
@@ -154,6 +557,7 @@ struct WCOutput {
     line_ct: u64,
     word_ct: u64,
     char_ct: u64,
+    max_line_len: u64,
     filename: Option<String>,
 }
 
@@ -173,6 +577,9 @@ impl WCOutput {
         } else if wc.bytes || default {
             out.push_str(format!("\t{}", self.byte_ct).as_str());
         }
+        if wc.max_line {
+            out.push_str(format!("\t{}", self.max_line_len).as_str());
+        }
         if let Some(f) = &self.filename {
             out.push_str(format!(" {}", f).as_str())
         }
@@ -183,15 +590,18 @@ impl WCOutput {
 fn main() -> Result<(), Box<dyn Error>> {
     let args = env::args().collect();
     let mut wc = WCCmd::from_args(args)?;
-    wc.process()?;
+    let had_error = wc.process();
     wc.show();
+    if had_error {
+        std::process::exit(1);
+    }
     Ok(())
 }
 
 #[cfg(test)]
 mod tests {
 
-    use crate::{WCCmd, WCInput, WCOutput};
+    use crate::{quote_filename, Utf8Decoder, WCCmd, WCCountFlags, WCInput, WCOutput, CHUNK_SIZE};
 
     #[test]
     fn wccmd_parse_env_args_test() {
@@ -201,6 +611,87 @@ mod tests {
         _ = wc;
     }
 
+    #[test]
+    fn wccmd_files0_from_splits_on_nul_test() {
+        let dir = std::env::temp_dir();
+        let list_path = dir.join("ccwc_files0_from_test.list");
+        std::fs::write(&list_path, b"a.txt\0b.txt\0").unwrap();
+
+        let args = vec![
+            String::from("ccwc"),
+            format!("--files0-from={}", list_path.to_str().unwrap()),
+        ];
+        let wc = WCCmd::from_args(args).unwrap();
+
+        assert_eq!(wc.inputs.len(), 2);
+        assert_eq!(wc.inputs[0].path().unwrap(), "a.txt");
+        assert_eq!(wc.inputs[1].path().unwrap(), "b.txt");
+
+        std::fs::remove_file(&list_path).unwrap();
+    }
+
+    #[test]
+    fn wccmd_files0_from_rejects_interior_empty_name_test() {
+        let dir = std::env::temp_dir();
+        let list_path = dir.join("ccwc_files0_from_empty_test.list");
+        std::fs::write(&list_path, b"a.txt\0\0b.txt\0").unwrap();
+
+        let args = vec![
+            String::from("ccwc"),
+            format!("--files0-from={}", list_path.to_str().unwrap()),
+        ];
+        assert!(WCCmd::from_args(args).is_err());
+
+        std::fs::remove_file(&list_path).unwrap();
+    }
+
+    #[test]
+    fn wccmd_files0_from_rejects_extra_operand_test() {
+        let args = vec![
+            String::from("ccwc"),
+            String::from("--files0-from=list.txt"),
+            String::from("extra.txt"),
+        ];
+        assert!(WCCmd::from_args(args).is_err());
+    }
+
+    #[test]
+    fn wccmd_total_output_sums_outputs_test() {
+        let mut wc = WCCmd::default();
+        wc.outputs.push(WCOutput {
+            line_ct: 1,
+            word_ct: 2,
+            byte_ct: 3,
+            char_ct: 3,
+            filename: Some("a.txt".to_string()),
+            ..WCOutput::default()
+        });
+        wc.outputs.push(WCOutput {
+            line_ct: 4,
+            word_ct: 5,
+            byte_ct: 6,
+            char_ct: 6,
+            filename: Some("b.txt".to_string()),
+            ..WCOutput::default()
+        });
+
+        let total = wc.total_output();
+        assert_eq!(total.line_ct, 5);
+        assert_eq!(total.word_ct, 7);
+        assert_eq!(total.byte_ct, 9);
+        assert_eq!(total.filename.unwrap(), "total");
+    }
+
+    #[test]
+    fn wccmd_total_rejects_invalid_when_test() {
+        let args = vec![
+            String::from("ccwc"),
+            String::from("--total=bogus"),
+            String::from("a.txt"),
+        ];
+        assert!(WCCmd::from_args(args).is_err());
+    }
+
     #[test]
     fn input_path_test() {
         let filepath = String::from("test.file");
@@ -211,6 +702,47 @@ mod tests {
         assert_eq!(None, stdin_input.path());
     }
 
+    #[test]
+    fn wcinput_reader_reports_missing_file_test() {
+        let input = WCInput::File(String::from("does_not_exist.file"));
+        let err = match input.reader() {
+            Err(e) => e,
+            Ok(_) => panic!("expected an error"),
+        };
+
+        assert_eq!(
+            err.to_string(),
+            "does_not_exist.file: No such file or directory"
+        );
+    }
+
+    #[test]
+    fn wccmd_process_continues_after_missing_file_test() {
+        let dir = std::env::temp_dir();
+        let good_path = dir.join("ccwc_process_continues_test.file");
+        std::fs::write(&good_path, b"hello\n").unwrap();
+
+        let mut wc = WCCmd {
+            inputs: vec![
+                WCInput::File(String::from("does_not_exist.file")),
+                WCInput::File(good_path.to_str().unwrap().to_string()),
+            ],
+            ..WCCmd::default()
+        };
+
+        let had_error = wc.process();
+        assert!(had_error);
+        assert_eq!(wc.outputs.len(), 1);
+
+        std::fs::remove_file(&good_path).unwrap();
+    }
+
+    #[test]
+    fn quote_filename_wraps_names_with_spaces_test() {
+        assert_eq!(quote_filename("plain.txt"), "plain.txt");
+        assert_eq!(quote_filename("has space.txt"), "'has space.txt'");
+    }
+
     #[test]
     fn wcoutput_print_using_default_test() {
         let wc = WCCmd::default();
@@ -223,11 +755,15 @@ mod tests {
 
     #[test]
     fn wcoutput_print_using_chars_test() {
-        let mut wc = WCCmd::default();
-        wc.chars = true;
+        let wc = WCCmd {
+            chars: true,
+            ..WCCmd::default()
+        };
 
-        let mut out = WCOutput::default();
-        out.char_ct = 123;
+        let out = WCOutput {
+            char_ct: 123,
+            ..WCOutput::default()
+        };
 
         let result = out.as_string(&wc);
         let expected = "\t123";
@@ -238,11 +774,189 @@ mod tests {
     fn wcoutput_print_with_input_file_test() {
         let input = Some(String::from("./test_file.txt"));
         let wc = WCCmd::default();
-        let mut out = WCOutput::default();
-        out.filename = input.clone();
+        let out = WCOutput {
+            filename: input.clone(),
+            ..WCOutput::default()
+        };
 
         let result = out.as_string(&wc);
         let expected = format!("\t0\t0\t0 {}", input.unwrap());
         assert_eq!(result, expected);
     }
+
+    #[test]
+    fn wcoutput_print_using_max_line_test() {
+        let wc = WCCmd {
+            max_line: true,
+            ..WCCmd::default()
+        };
+
+        let out = WCOutput {
+            max_line_len: 42,
+            ..WCOutput::default()
+        };
+
+        let result = out.as_string(&wc);
+        let expected = "\t42";
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn wcoutput_print_using_max_line_with_lines_test() {
+        let wc = WCCmd {
+            max_line: true,
+            lines: true,
+            ..WCCmd::default()
+        };
+
+        let out = WCOutput {
+            line_ct: 7,
+            max_line_len: 42,
+            ..WCOutput::default()
+        };
+
+        let result = out.as_string(&wc);
+        let expected = "\t7\t42";
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn wccmd_count_general_max_line_expands_tabs_and_ignores_combining_test() {
+        let max_line_of = |s: &str| {
+            let mut output = WCOutput::default();
+            let mut reader = std::io::Cursor::new(s.as_bytes().to_vec());
+            WCCmd::count_general(
+                &mut reader,
+                WCCountFlags {
+                    count_max_line: true,
+                    ..WCCountFlags::default()
+                },
+                &mut output,
+            )
+            .unwrap();
+            output.max_line_len
+        };
+
+        assert_eq!(max_line_of("hello\nworld!"), 6);
+        assert_eq!(max_line_of("a\tb"), 9);
+        assert_eq!(max_line_of("e\u{0301}e\u{0301}"), 2);
+    }
+
+    #[test]
+    fn wccmd_count_fast_matches_whole_buffer_counting_over_chunk_boundaries_test() {
+        let mut line = vec![b'a'; 100];
+        line.push(b'\n');
+        let data: Vec<u8> = line
+            .iter()
+            .cycle()
+            .take(line.len() * 5000)
+            .copied()
+            .collect();
+        assert!(data.len() > crate::CHUNK_SIZE * 2);
+
+        let expected_bytes = data.len() as u64;
+        let expected_lines = data.iter().filter(|b| **b == b'\n').count() as u64;
+
+        let mut output = WCOutput::default();
+        let mut reader = std::io::Cursor::new(data);
+        WCCmd::count_fast(&mut reader, true, true, &mut output).unwrap();
+
+        assert_eq!(output.byte_ct, expected_bytes);
+        assert_eq!(output.line_ct, expected_lines);
+    }
+
+    #[test]
+    fn utf8_decoder_reassembles_char_split_across_feed_calls_test() {
+        let euro = "€".as_bytes(); // 3 bytes: 0xE2 0x82 0xAC
+        assert_eq!(euro.len(), 3);
+
+        let mut decoder = Utf8Decoder::default();
+        let mut chars = Vec::new();
+        decoder.feed(&euro[..1], |c| chars.push(c));
+        decoder.feed(&euro[1..], |c| chars.push(c));
+        decoder.finish(|c| chars.push(c));
+
+        assert_eq!(chars, vec!['€']);
+    }
+
+    #[test]
+    fn utf8_decoder_resyncs_after_invalid_byte_test() {
+        let mut decoder = Utf8Decoder::default();
+        let mut chars = Vec::new();
+        decoder.feed(&[b'a', 0xFF, b'b'], |c| chars.push(c));
+        decoder.finish(|c| chars.push(c));
+
+        assert_eq!(chars, vec!['a', '\u{FFFD}', 'b']);
+    }
+
+    #[test]
+    fn utf8_decoder_flushes_truncated_trailing_sequence_test() {
+        let euro = "€".as_bytes();
+        let mut decoder = Utf8Decoder::default();
+        let mut chars = Vec::new();
+        decoder.feed(&euro[..2], |c| chars.push(c));
+        decoder.finish(|c| chars.push(c));
+
+        assert_eq!(chars, vec!['\u{FFFD}']);
+    }
+
+    #[test]
+    fn wccmd_count_general_counts_multibyte_words_and_chars_split_across_chunks_test() {
+        let word = "café".repeat(CHUNK_SIZE / 4 + 10);
+        let data = format!("{word} {word}\n").into_bytes();
+
+        let mut output = WCOutput::default();
+        let mut reader = std::io::Cursor::new(data.clone());
+        WCCmd::count_general(
+            &mut reader,
+            WCCountFlags {
+                count_bytes: true,
+                count_lines: true,
+                count_words: true,
+                count_chars: true,
+                ..WCCountFlags::default()
+            },
+            &mut output,
+        )
+        .unwrap();
+
+        let expected_chars = String::from_utf8(data).unwrap().chars().count() as u64;
+        assert_eq!(output.char_ct, expected_chars);
+        assert_eq!(output.word_ct, 2);
+        assert_eq!(output.line_ct, 1);
+    }
+
+    #[test]
+    fn wccmd_count_general_leading_whitespace_not_counted_as_word_test() {
+        let mut output = WCOutput::default();
+        let mut reader = std::io::Cursor::new(b"   hello world\n".to_vec());
+        WCCmd::count_general(
+            &mut reader,
+            WCCountFlags {
+                count_words: true,
+                ..WCCountFlags::default()
+            },
+            &mut output,
+        )
+        .unwrap();
+
+        assert_eq!(output.word_ct, 2);
+    }
+
+    #[test]
+    fn wccmd_count_general_counts_final_word_without_trailing_whitespace_test() {
+        let mut output = WCOutput::default();
+        let mut reader = std::io::Cursor::new(b"a b c".to_vec());
+        WCCmd::count_general(
+            &mut reader,
+            WCCountFlags {
+                count_words: true,
+                ..WCCountFlags::default()
+            },
+            &mut output,
+        )
+        .unwrap();
+
+        assert_eq!(output.word_ct, 3);
+    }
 }